@@ -0,0 +1,102 @@
+//
+// chips.rs: Per-device descriptors for the GAL parts we support.
+//
+// Everything that `make_jedec` needs to know about a device -- the
+// shape of its fuse map, how many fuses it has in total, and which
+// extra fuse banks (AC1/PT/SYN/AC0) it carries -- lives in one
+// `ChipSpec` per `Chip`, rather than being scattered across several
+// `match gal_type` blocks.
+//
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Chip {
+    GAL16V8,
+    GAL20V8,
+    GAL22V10,
+    GAL20RA10,
+}
+
+impl Chip {
+    // The C side identifies devices with these small integers.
+    pub fn from_raw(value: i32) -> Option<Chip> {
+        match value {
+            1 => Some(Chip::GAL16V8),
+            2 => Some(Chip::GAL20V8),
+            3 => Some(Chip::GAL22V10),
+            4 => Some(Chip::GAL20RA10),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ChipSpec {
+    // Name as printed in the JEDEC header ("Device:         <name>").
+    pub device_name: &'static str,
+    // Number of fuses per row of the AND array.
+    pub row_len: usize,
+    // Number of rows in the AND array.
+    pub num_rows: usize,
+    // Total number of fuses on the device, i.e. the `*QF` count.
+    pub total_fuses: usize,
+    // Number of XOR (output polarity) fuses.
+    pub xor_size: usize,
+    // GAL22V10 interleaves an S1 fuse with every XOR fuse.
+    pub has_s1: bool,
+    // GAL16V8/GAL20V8 carry AC1, PT, SYN and AC0 fuse banks.
+    pub has_ac_bits: bool,
+}
+
+const GAL16V8_SPEC: ChipSpec = ChipSpec {
+    device_name: "GAL16V8",
+    row_len: 32,
+    num_rows: 64,
+    total_fuses: 2194,
+    xor_size: 8,
+    has_s1: false,
+    has_ac_bits: true,
+};
+
+const GAL20V8_SPEC: ChipSpec = ChipSpec {
+    device_name: "GAL20V8",
+    row_len: 40,
+    num_rows: 64,
+    total_fuses: 2706,
+    xor_size: 8,
+    has_s1: false,
+    has_ac_bits: true,
+};
+
+const GAL22V10_SPEC: ChipSpec = ChipSpec {
+    device_name: "GAL22V10",
+    row_len: 44,
+    num_rows: 132,
+    total_fuses: 5892,
+    xor_size: 10,
+    has_s1: true,
+    has_ac_bits: false,
+};
+
+const GAL20RA10_SPEC: ChipSpec = ChipSpec {
+    device_name: "GAL20RA10",
+    row_len: 40,
+    num_rows: 80,
+    total_fuses: 3274,
+    xor_size: 10,
+    has_s1: false,
+    has_ac_bits: false,
+};
+
+// Signature fuses are the same size on every device we support.
+pub const SIG_SIZE: usize = 64;
+pub const AC1_SIZE: usize = 8;
+pub const PT_SIZE: usize = 64;
+
+pub fn spec(chip: Chip) -> &'static ChipSpec {
+    match chip {
+        Chip::GAL16V8 => &GAL16V8_SPEC,
+        Chip::GAL20V8 => &GAL20V8_SPEC,
+        Chip::GAL22V10 => &GAL22V10_SPEC,
+        Chip::GAL20RA10 => &GAL20RA10_SPEC,
+    }
+}
@@ -7,13 +7,20 @@
 // error code with the line number.
 //
 
-#[derive(Clone, Copy, Debug)]
+use std::error;
+use std::fmt;
+
+use messages;
+
+#[derive(Clone, Debug)]
 pub struct Error {
     pub code: ErrorCode,
     pub line: u32,
+    pub column: u32,
+    pub snippet: Option<String>,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ErrorCode {
     ARSPAsPinName,
     ARSPSuffix,
@@ -22,15 +29,19 @@ pub enum ErrorCode {
     BadChar,
     BadEOF,
     BadEOL,
+    BadFuseAddress,
+    BadFuseChecksum,
     BadGALType,
     BadGND,
     BadGNDLocation,
+    BadJedecFrame,
     BadNC,
     BadPin,
     BadPinCount,
     BadPower,
     BadSuffix,
     BadToken,
+    BadTransmissionChecksum,
     BadVCC,
     BadVCCLocation,
     DisallowedAPRST,
@@ -44,12 +55,12 @@ pub enum ErrorCode {
     NoCLK,
     NoEquals,
     NoPinName,
-    NotAnInput1,
-    NotAnInput111,
-    NotAnInput113,
-    NotAnInput1219,
-    NotAnInput13,
-    NotAnInput1522,
+    // GAL20RA10: the given pin can't be used in equations.
+    NotAnInputPin(u32),
+    // mode 3: the two given pins are reserved for 'Clock' and '/OE'.
+    NotAnInputClockOE(u32, u32),
+    // mode 2: the two given pins can't be used as input.
+    NotAnInputMode2(u32, u32),
     NotAnOutput,
     RepeatedAPRST,
     RepeatedARSP,
@@ -68,67 +79,80 @@ pub enum ErrorCode {
     UnmatchedTristate,
 }
 
-fn error_string(err_code: ErrorCode) -> &'static str {
-    match err_code {
-        ErrorCode::ARSPAsPinName => "GAL22V10: AR and SP is not allowed as pinname",
-        ErrorCode::ARSPSuffix => "AR, SP: no suffix allowed",
-        ErrorCode::BadAnalysis => "internal error: analyse_mode should never let you use this pin as an input",
-        ErrorCode::BadARSP => "use of AR and SP is not allowed in equations",
-        ErrorCode::BadNC => "NC (Not Connected) is not allowed in logic equations",
-        ErrorCode::BadChar => "bad character in input",
-        ErrorCode::BadEOF => "unexpected end of file",
-        ErrorCode::BadEOL => "unexpected end of line",
-        ErrorCode::BadGALType => "Line  1: type of GAL expected",
-        ErrorCode::BadPin => "illegal character in pin declaration",
-        ErrorCode::BadPinCount => "wrong number of pins",
-        ErrorCode::BadPower => "use of VCC and GND is not allowed in equations",
-        ErrorCode::BadSuffix => "unknown suffix found",
-        ErrorCode::BadToken => "unexpected token",
-        ErrorCode::InvertedARSP => "negation of AR and SP is not allowed",
-        ErrorCode::InvalidControl => "use of .CLK, .ARST, .APRST only allowed for registered outputs",
-        ErrorCode::InvertedControl => ".E, .CLK, .ARST and .APRST is not allowed to be negated",
-        ErrorCode::InvertedPower => "use GND, VCC instead of /VCC, /GND",
-        ErrorCode::MoreThanOneProduct => "only one product term allowed (no OR)",
-        ErrorCode::NotAnInput1 => "GAL20RA10: pin 1 can't be used in equations",
-        ErrorCode::NotAnInput111 => "mode 3: pins 1,11 are reserved for 'Clock' and '/OE'",
-        ErrorCode::NotAnInput113 => "mode 3: pins 1,13 are reserved for 'Clock' and '/OE'",
-        ErrorCode::NotAnInput1219 => "mode 2: pins 12, 19 can't be used as input",
-        ErrorCode::NotAnInput13 => "GAL20RA10: pin 13 can't be used in equations",
-        ErrorCode::NotAnInput1522 => "mode 2: pins 15, 22 can't be used as input",
-        ErrorCode::NotAnOutput => "this pin can't be used as output",
-        ErrorCode::NoCLK => "missing clock definition (.CLK) of registered output",
-        ErrorCode::NoPinName => "pinname expected after '/'",
-        ErrorCode::NoEquals => "'=' expected",
-        ErrorCode::RepeatedAPRST => "several .APRST definitions for the same output found",
-        ErrorCode::RepeatedARST => "several .ARST definitions for the same output found",
-        ErrorCode::RepeatedARSP => "AR or SP is defined twice",
-        ErrorCode::RepeatedCLK => "several .CLK definitions for the same output found",
-        ErrorCode::RepeatedOutput => "same pin is defined multible as output",
-        ErrorCode::RepeatedPinName => "pinname defined twice",
-        ErrorCode::RepeatedTristate => "tristate control is defined twice",
-        ErrorCode::SoloAPRST => "if using .APRST the output must be defined",
-        ErrorCode::SoloARST => "if using .ARST, the output must be defined",
-        ErrorCode::SoloCLK => "if using .CLK, the output must be defined",
-        ErrorCode::SoloEnable => "if using .E, the output must be defined",
-        ErrorCode::TooManyProducts => "too many product terms",
-        ErrorCode::TristateReg => "GAL16V8/20V8: tri. control for reg. output is not allowed",
-        ErrorCode::UnknownPin => "unknown pinname",
-        ErrorCode::UnmatchedTristate => "tristate control without previous '.T'",
-        ErrorCode::BadVCC => "pin declaration: expected VCC at VCC pin",
-        ErrorCode::BadVCCLocation => "illegal VCC/GND assignment",
-        ErrorCode::BadGND => "pin declaration: expected GND at GND pin",
-        ErrorCode::BadGNDLocation => "illegal VCC/GND assignment",
-        ErrorCode::DisallowedCLK => ".CLK is not allowed when this type of GAL is used",
-        ErrorCode::DisallowedARST => ".ARST is not allowed when this type of GAL is used",
-        ErrorCode::DisallowedAPRST => ".APRST is not allowed when this type of GAL is used",
+// Render an `ErrorCode` as English text. This is the catalog's
+// built-in default and fallback; see `messages::error_string_in` for
+// the locale-aware version callers should prefer.
+pub fn error_string(err_code: ErrorCode) -> String {
+    messages::error_string_in(err_code, messages::Locale::En)
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Error in line {}, column {}: {}",
+            self.line,
+            self.column,
+            messages::error_string_in(self.code, messages::locale())
+        )?;
+        if let Some(ref snippet) = self.snippet {
+            write!(f, "\n{}\n{}^", snippet, " ".repeat(self.column as usize))?;
+        }
+        Ok(())
     }
 }
 
-// Adapt an ErrorCode to an Error.
+impl error::Error for Error {}
+
+// Adapt an ErrorCode to an Error, with no column or source context.
 pub fn at_line<Val>(line: u32, res: Result<Val, ErrorCode>) -> Result<Val, Error> {
-   res.map_err(|e| Error { code: e, line: line })
+    at_span(line, 0, None, res)
+}
+
+// Adapt an ErrorCode to an Error carrying the column of the offending
+// token and, if available, the source line it was found on, so callers
+// can render a caret pointing at the bad character.
+pub fn at_span<Val>(
+    line: u32,
+    column: u32,
+    snippet: Option<&str>,
+    res: Result<Val, ErrorCode>,
+) -> Result<Val, Error> {
+    res.map_err(|e| Error {
+        code: e,
+        line: line,
+        column: column,
+        snippet: snippet.map(|s| s.to_string()),
+    })
 }
 
-pub fn print_error(err: Error) {
-    println!("Error in line {}: {}", err.line, error_string(err.code));
+// Default renderer for an Error. Callers embedding galette as a library
+// are free to ignore this and use the `Display` impl directly instead.
+pub fn print_error(err: &Error) {
+    println!("{}", err);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn at_span_renders_a_caret_under_the_offending_column() {
+        let err = at_span(
+            3,
+            5,
+            Some("AR = 1"),
+            Err::<(), ErrorCode>(ErrorCode::BadARSP),
+        )
+        .unwrap_err();
+
+        assert_eq!(err.line, 3);
+        assert_eq!(err.column, 5);
+
+        let rendered = err.to_string();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[1], "AR = 1");
+        // The caret line is all spaces up to `column`, then a single `^`.
+        assert_eq!(lines[2], format!("{}^", " ".repeat(5)));
+    }
 }
@@ -0,0 +1,353 @@
+//
+// jedec_reader.rs: Parse a JEDEC file back into fuse data.
+//
+// This is the inverse of `jedec_writer::make_jedec`: it consumes the
+// byte stream `make_jedec` produces and reconstructs the same fuse
+// bitvectors, so a `galette -> jed -> galette` round trip can be
+// verified, and so other tools can disassemble an existing JEDEC file.
+//
+
+use chips::{self, Chip};
+use errors::ErrorCode;
+use jedec_writer::CheckSummer;
+
+// The fuse data recovered from a JEDEC file, in the same shape that
+// `jedec_writer::make_jedec` consumes.
+#[derive(Debug)]
+pub struct JedecFile {
+    pub sec_bit: bool,
+    pub fuses: Vec<bool>,
+    pub xor: Vec<bool>,
+    pub s1: Vec<bool>,
+    pub sig: Vec<bool>,
+    pub ac1: Vec<bool>,
+    pub pt: Vec<bool>,
+    pub syn: bool,
+    pub ac0: bool,
+}
+
+pub fn parse_jedec(gal_type: Chip, data: &[u8]) -> Result<JedecFile, ErrorCode> {
+    let spec = chips::spec(gal_type);
+
+    let stx = data
+        .iter()
+        .position(|&b| b == 0x02)
+        .ok_or(ErrorCode::BadJedecFrame)?;
+    let etx = data
+        .iter()
+        .position(|&b| b == 0x03)
+        .ok_or(ErrorCode::BadJedecFrame)?;
+    if etx < stx {
+        return Err(ErrorCode::BadJedecFrame);
+    }
+
+    verify_transmission_checksum(&data[stx..=etx], &data[etx + 1..])?;
+
+    let body = ::std::str::from_utf8(&data[stx + 1..etx]).map_err(|_| ErrorCode::BadJedecFrame)?;
+
+    let mut fuses = vec![false; spec.total_fuses];
+    let mut sec_bit = false;
+    let mut declared_checksum: Option<u16> = None;
+
+    for field in body.split('*').map(str::trim).filter(|f| !f.is_empty()) {
+        if field == "F0" {
+            // Default fuse value is already false.
+        } else if field == "F1" {
+            for fuse in fuses.iter_mut() {
+                *fuse = true;
+            }
+        } else if let Some(state) = field.strip_prefix('G') {
+            sec_bit = state.trim() == "1";
+        } else if field.starts_with("QF") {
+            // Informational count; the ChipSpec is authoritative.
+        } else if let Some(rest) = field.strip_prefix('L') {
+            let mut parts = rest.trim_start().splitn(2, char::is_whitespace);
+            let addr_str = parts.next().ok_or(ErrorCode::BadFuseAddress)?;
+            let addr: usize = addr_str.parse().map_err(|_| ErrorCode::BadFuseAddress)?;
+            let bits = parts.next().unwrap_or("").trim();
+            for (i, c) in bits.chars().enumerate() {
+                let idx = addr + i;
+                if idx >= spec.total_fuses {
+                    return Err(ErrorCode::BadFuseAddress);
+                }
+                fuses[idx] = c == '1';
+            }
+        } else if let Some(rest) = field.strip_prefix('C') {
+            let sum = u16::from_str_radix(rest.trim(), 16).map_err(|_| ErrorCode::BadFuseChecksum)?;
+            declared_checksum = Some(sum);
+        }
+        // Any other field (e.g. the bare trailing `*`) carries no data.
+    }
+
+    // Every file `make_jedec` emits carries a `*C` field; treat a
+    // missing one as a verification failure rather than silently
+    // skipping the check.
+    let declared = declared_checksum.ok_or(ErrorCode::BadFuseChecksum)?;
+    let mut checksum = CheckSummer::new();
+    for fuse in &fuses {
+        checksum.add(*fuse);
+    }
+    if checksum.get() != declared {
+        return Err(ErrorCode::BadFuseChecksum);
+    }
+
+    let mut fuses = fuses.into_iter();
+    let main_len = spec.row_len * spec.num_rows;
+    let gal_fuses: Vec<bool> = fuses.by_ref().take(main_len).collect();
+
+    let (xor, s1) = if spec.has_s1 {
+        let mut xor = Vec::with_capacity(spec.xor_size);
+        let mut s1 = Vec::with_capacity(spec.xor_size);
+        for _ in 0..spec.xor_size {
+            xor.push(fuses.next().ok_or(ErrorCode::BadFuseAddress)?);
+            s1.push(fuses.next().ok_or(ErrorCode::BadFuseAddress)?);
+        }
+        (xor, s1)
+    } else {
+        let xor: Vec<bool> = fuses.by_ref().take(spec.xor_size).collect();
+        (xor, Vec::new())
+    };
+
+    let sig: Vec<bool> = fuses.by_ref().take(chips::SIG_SIZE).collect();
+
+    let (ac1, pt, syn, ac0) = if spec.has_ac_bits {
+        let ac1: Vec<bool> = fuses.by_ref().take(chips::AC1_SIZE).collect();
+        let pt: Vec<bool> = fuses.by_ref().take(chips::PT_SIZE).collect();
+        let syn = fuses.next().ok_or(ErrorCode::BadFuseAddress)?;
+        let ac0 = fuses.next().ok_or(ErrorCode::BadFuseAddress)?;
+        (ac1, pt, syn, ac0)
+    } else {
+        (Vec::new(), Vec::new(), false, false)
+    };
+
+    Ok(JedecFile {
+        sec_bit: sec_bit,
+        fuses: gal_fuses,
+        xor: xor,
+        s1: s1,
+        sig: sig,
+        ac1: ac1,
+        pt: pt,
+        syn: syn,
+        ac0: ac0,
+    })
+}
+
+// The transmission checksum is the 16-bit sum, modulo 65536, of every
+// byte from STX through ETX inclusive, printed as four hex digits in
+// the trailer that follows ETX.
+fn verify_transmission_checksum(body: &[u8], trailer: &[u8]) -> Result<(), ErrorCode> {
+    let computed = body
+        .iter()
+        .fold(0u16, |acc, &b| acc.wrapping_add(b as u16));
+
+    let trailer_str =
+        ::std::str::from_utf8(trailer).map_err(|_| ErrorCode::BadTransmissionChecksum)?;
+    let declared = u16::from_str_radix(trailer_str.trim(), 16)
+        .map_err(|_| ErrorCode::BadTransmissionChecksum)?;
+
+    if declared != computed {
+        return Err(ErrorCode::BadTransmissionChecksum);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jedec_writer::{make_jedec, Config};
+
+    fn config(sec_bit: bool) -> Config {
+        Config {
+            gen_fuse: 0,
+            gen_chip: 0,
+            gen_pin: 0,
+            jedec_sec_bit: if sec_bit { 1 } else { 0 },
+            jedec_fuse_chk: 0,
+        }
+    }
+
+    // A non-uniform pattern, so a round trip that silently reordered or
+    // truncated fuses wouldn't accidentally still match.
+    fn pattern(len: usize, offset: usize) -> Vec<bool> {
+        (0..len).map(|i| (i + offset) % 3 == 0).collect()
+    }
+
+    fn round_trip(gal_type: Chip) {
+        let spec = chips::spec(gal_type);
+        let main_len = spec.row_len * spec.num_rows;
+
+        let gal_fuses = pattern(main_len, 0);
+        let gal_xor = pattern(spec.xor_size, 1);
+        let gal_s1 = pattern(spec.xor_size, 2);
+        let gal_sig = pattern(chips::SIG_SIZE, 0);
+        let gal_ac1 = pattern(chips::AC1_SIZE, 1);
+        let gal_pt = pattern(chips::PT_SIZE, 2);
+        let gal_syn = true;
+        let gal_ac0 = false;
+
+        let jedec = make_jedec(
+            gal_type,
+            &config(true),
+            &gal_fuses,
+            &gal_xor,
+            &gal_s1,
+            &gal_sig,
+            &gal_ac1,
+            &gal_pt,
+            gal_syn,
+            gal_ac0,
+        );
+
+        let parsed = parse_jedec(gal_type, jedec.as_bytes()).expect("valid JEDEC file should parse");
+
+        assert_eq!(parsed.sec_bit, true);
+        assert_eq!(parsed.fuses, gal_fuses);
+        assert_eq!(parsed.xor, gal_xor);
+        assert_eq!(parsed.sig, gal_sig);
+        if spec.has_s1 {
+            assert_eq!(parsed.s1, gal_s1);
+        } else {
+            assert_eq!(parsed.s1, Vec::<bool>::new());
+        }
+        if spec.has_ac_bits {
+            assert_eq!(parsed.ac1, gal_ac1);
+            assert_eq!(parsed.pt, gal_pt);
+            assert_eq!(parsed.syn, gal_syn);
+            assert_eq!(parsed.ac0, gal_ac0);
+        } else {
+            assert_eq!(parsed.ac1, Vec::<bool>::new());
+            assert_eq!(parsed.pt, Vec::<bool>::new());
+            assert_eq!(parsed.syn, false);
+            assert_eq!(parsed.ac0, false);
+        }
+    }
+
+    #[test]
+    fn round_trips_every_chip() {
+        round_trip(Chip::GAL16V8);
+        round_trip(Chip::GAL20V8);
+        round_trip(Chip::GAL22V10);
+        round_trip(Chip::GAL20RA10);
+    }
+
+    // Flip one hex digit of the *C fuse checksum so it no longer
+    // matches the fuses in the file, then patch up the transmission
+    // checksum so only the fuse checksum check can catch it.
+    fn corrupt_fuse_checksum(jedec: &str) -> String {
+        let start = jedec.find("*C").unwrap() + 2;
+        let mut bytes = jedec.as_bytes().to_vec();
+        bytes[start] = if bytes[start] == b'0' { b'1' } else { b'0' };
+
+        let stx = bytes.iter().position(|&b| b == 0x02).unwrap();
+        let etx = bytes.iter().position(|&b| b == 0x03).unwrap();
+        let transmission_checksum = bytes[stx..=etx]
+            .iter()
+            .fold(0u16, |acc, &b| acc.wrapping_add(b as u16));
+        let trailer = format!("{:04x}\n", transmission_checksum);
+        bytes.splice(etx + 1.., trailer.into_bytes());
+
+        String::from_utf8(bytes).unwrap()
+    }
+
+    // Flip one hex digit of the trailing transmission checksum.
+    fn corrupt_transmission_checksum(jedec: &str) -> String {
+        let etx = jedec.find('\x03').unwrap();
+        let mut bytes = jedec.as_bytes().to_vec();
+        let pos = etx + 1;
+        bytes[pos] = if bytes[pos] == b'0' { b'1' } else { b'0' };
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn rejects_corrupted_fuse_checksum() {
+        let spec = chips::spec(Chip::GAL16V8);
+        let main_len = spec.row_len * spec.num_rows;
+        let jedec = make_jedec(
+            Chip::GAL16V8,
+            &config(false),
+            &vec![false; main_len],
+            &vec![false; spec.xor_size],
+            &[],
+            &vec![false; chips::SIG_SIZE],
+            &vec![false; chips::AC1_SIZE],
+            &vec![false; chips::PT_SIZE],
+            false,
+            false,
+        );
+
+        let corrupted = corrupt_fuse_checksum(&jedec);
+        assert_eq!(
+            parse_jedec(Chip::GAL16V8, corrupted.as_bytes()).unwrap_err(),
+            ErrorCode::BadFuseChecksum
+        );
+    }
+
+    #[test]
+    fn rejects_corrupted_transmission_checksum() {
+        let spec = chips::spec(Chip::GAL16V8);
+        let main_len = spec.row_len * spec.num_rows;
+        let jedec = make_jedec(
+            Chip::GAL16V8,
+            &config(false),
+            &vec![false; main_len],
+            &vec![false; spec.xor_size],
+            &[],
+            &vec![false; chips::SIG_SIZE],
+            &vec![false; chips::AC1_SIZE],
+            &vec![false; chips::PT_SIZE],
+            false,
+            false,
+        );
+
+        let corrupted = corrupt_transmission_checksum(&jedec);
+        assert_eq!(
+            parse_jedec(Chip::GAL16V8, corrupted.as_bytes()).unwrap_err(),
+            ErrorCode::BadTransmissionChecksum
+        );
+    }
+
+    // Drop the `*C` field entirely, then patch up the transmission
+    // checksum so only the missing-fuse-checksum path can catch it.
+    fn remove_fuse_checksum_field(jedec: &str) -> String {
+        let start = jedec.find("*C").unwrap();
+        let len = jedec[start..].find('\n').unwrap() + 1;
+        let mut bytes = jedec.as_bytes().to_vec();
+        bytes.splice(start..start + len, std::iter::empty());
+
+        let stx = bytes.iter().position(|&b| b == 0x02).unwrap();
+        let etx = bytes.iter().position(|&b| b == 0x03).unwrap();
+        let transmission_checksum = bytes[stx..=etx]
+            .iter()
+            .fold(0u16, |acc, &b| acc.wrapping_add(b as u16));
+        let trailer = format!("{:04x}\n", transmission_checksum);
+        bytes.splice(etx + 1.., trailer.into_bytes());
+
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn rejects_missing_fuse_checksum_field() {
+        let spec = chips::spec(Chip::GAL16V8);
+        let main_len = spec.row_len * spec.num_rows;
+        let jedec = make_jedec(
+            Chip::GAL16V8,
+            &config(false),
+            &vec![false; main_len],
+            &vec![false; spec.xor_size],
+            &[],
+            &vec![false; chips::SIG_SIZE],
+            &vec![false; chips::AC1_SIZE],
+            &vec![false; chips::PT_SIZE],
+            false,
+            false,
+        );
+
+        let missing = remove_fuse_checksum_field(&jedec);
+        assert_eq!(
+            parse_jedec(Chip::GAL16V8, missing.as_bytes()).unwrap_err(),
+            ErrorCode::BadFuseChecksum
+        );
+    }
+}
@@ -1,14 +1,8 @@
 extern crate itertools;
 
-use chips::Chip;
+use chips::{self, Chip};
 use self::itertools::Itertools;
 
-// Number of fuses per-row.
-const ROW_LEN_ADR16: usize = 32;
-const ROW_LEN_ADR20: usize = 40;
-const ROW_LEN_ADR22V10: usize = 44;
-const ROW_LEN_ADR20RA10: usize = 40;
-
 // Config use on the C side.
 #[repr(C)]
 #[derive(Debug)]
@@ -23,14 +17,14 @@ pub struct Config {
 ////////////////////////////////////////////////////////////////////////
 // Structure to track the fuse checksum.
 
-struct CheckSummer {
+pub(crate) struct CheckSummer {
     bit_num: u8,
     byte: u8,
     sum: u16,
 }
 
 impl CheckSummer {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         CheckSummer {
             bit_num: 0,
             byte: 0,
@@ -38,7 +32,7 @@ impl CheckSummer {
         }
     }
 
-    fn add(&mut self, bit: bool) {
+    pub(crate) fn add(&mut self, bit: bool) {
         if bit {
             self.byte |= 1 << self.bit_num
         };
@@ -50,7 +44,7 @@ impl CheckSummer {
         }
     }
 
-    fn get(&self) -> u16 {
+    pub(crate) fn get(&self) -> u16 {
         (self.sum + self.byte as u16) & 0xffff
     }
 }
@@ -126,12 +120,7 @@ pub fn make_jedec(
     gal_syn: bool,
     gal_ac0: bool,
 ) -> String {
-    let row_len = match gal_type {
-        Chip::GAL16V8 => ROW_LEN_ADR16,
-        Chip::GAL20V8 => ROW_LEN_ADR20,
-        Chip::GAL22V10 => ROW_LEN_ADR22V10,
-        Chip::GAL20RA10 => ROW_LEN_ADR20RA10,
-    };
+    let spec = chips::spec(gal_type);
 
     let mut buf = String::new();
 
@@ -140,12 +129,7 @@ pub fn make_jedec(
     // TODO: Backwards compatibility.
     buf.push_str("Used Program:   GALasm 2.1\n");
     buf.push_str("GAL-Assembler:  GALasm 2.1\n");
-    buf.push_str(match gal_type {
-        Chip::GAL16V8 => "Device:         GAL16V8\n\n",
-        Chip::GAL20V8 => "Device:         GAL20V8\n\n",
-        Chip::GAL22V10 => "Device:         GAL22V10\n\n",
-        Chip::GAL20RA10 => "Device:         GAL20RA10\n\n",
-    });
+    buf.push_str(&format!("Device:         {}\n\n", spec.device_name));
 
     // Default value of gal_fuses
     buf.push_str("*F0\n");
@@ -158,20 +142,14 @@ pub fn make_jedec(
     });
 
     // Number of fuses.
-    // TODO: Should be calculated.
-    buf.push_str(match gal_type {
-        Chip::GAL16V8 => "*QF2194\n",
-        Chip::GAL20V8 => "*QF2706\n",
-        Chip::GAL22V10 => "*QF5892\n",
-        Chip::GAL20RA10 => "*QF3274\n",
-    });
+    buf.push_str(&format!("*QF{}\n", spec.total_fuses));
 
     {
         // Construct fuse matrix.
         let mut fuse_builder = FuseBuilder::new(&mut buf);
 
         // Break the fuse map into chunks representing rows.
-        for row in &gal_fuses.iter().chunks(row_len) {
+        for row in &gal_fuses.iter().chunks(spec.row_len) {
             let (mut check_iter, mut print_iter) = row.tee();
 
             // Only write out non-zero bits.
@@ -184,7 +162,7 @@ pub fn make_jedec(
         }
 
         // XOR bits are interleaved with S1 bits on GAL22V10.
-        if gal_type != Chip::GAL22V10 {
+        if !spec.has_s1 {
             fuse_builder.add(gal_xor)
         } else {
             let bits = itertools::interleave(gal_xor.iter(), gal_s1.iter());
@@ -193,7 +171,7 @@ pub fn make_jedec(
 
         fuse_builder.add(gal_sig);
 
-        if (gal_type == Chip::GAL16V8) || (gal_type == Chip::GAL20V8) {
+        if spec.has_ac_bits {
             fuse_builder.add(gal_ac1);
             fuse_builder.add(gal_pt);
             fuse_builder.add(&[gal_syn]);
@@ -207,11 +185,111 @@ pub fn make_jedec(
     buf.push_str("*\n");
     buf.push('\x03');
 
-    // TODO: This should be a 16-bit checksum, but galasm does *not*
-    // do that. Standard says modulo 65535, a la TCP/IP, need to check
-    // what reading tools do.
-    let file_checksum = buf.as_bytes().iter().map(|c| *c as u32).sum::<u32>();
+    // Transmission checksum: the 16-bit sum, modulo 65536, of every
+    // byte from STX through ETX inclusive.
+    let file_checksum = buf
+        .as_bytes()
+        .iter()
+        .fold(0u16, |acc, &b| acc.wrapping_add(b as u16));
     buf.push_str(&format!("{:04x}\n", file_checksum));
 
     return buf;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        Config {
+            gen_fuse: 0,
+            gen_chip: 0,
+            gen_pin: 0,
+            jedec_sec_bit: 0,
+            jedec_fuse_chk: 0,
+        }
+    }
+
+    // A JEDEC file for an otherwise-blank GAL16V8: every fuse, the XOR,
+    // signature, AC1/PT/SYN/AC0 banks, the security bit, all zero. The
+    // `*C`/transmission checksum below were computed by hand against
+    // the JEDEC spec, not by calling any code in this crate, so a bug
+    // shared between `make_jedec` and a test helper can't hide here.
+    const BLANK_GAL16V8: &str = "\x02\n\
+Used Program:   GALasm 2.1\n\
+GAL-Assembler:  GALasm 2.1\n\
+Device:         GAL16V8\n\n\
+*F0\n\
+*G0\n\
+*QF2194\n\
+*L2048 00000000\n\
+*L2056 0000000000000000000000000000000000000000000000000000000000000000\n\
+*L2120 00000000\n\
+*L2128 0000000000000000000000000000000000000000000000000000000000000000\n\
+*L2192 0\n\
+*L2193 0\n\
+*C0000\n\
+*\n\
+\x033db7\n";
+
+    #[test]
+    fn blank_gal16v8_matches_known_good_jedec_file() {
+        let jedec = make_jedec(
+            Chip::GAL16V8,
+            &config(),
+            &[false; 2048],
+            &[false; 8],
+            &[],
+            &[false; chips::SIG_SIZE],
+            &[false; chips::AC1_SIZE],
+            &[false; chips::PT_SIZE],
+            false,
+            false,
+        );
+        assert_eq!(jedec, BLANK_GAL16V8);
+    }
+
+    // The `*C` fuse checksum and trailing transmission checksum for
+    // each device with every fuse, SYN and AC0 all set to 1 and the
+    // security bit left at 0. Computed by an independent script walking
+    // the JEDEC format, not derived from `CheckSummer`.
+    fn check_chip(gal_type: Chip, want_fuse_checksum: &str, want_transmission_checksum: &str) {
+        let spec = chips::spec(gal_type);
+        let main_len = spec.row_len * spec.num_rows;
+
+        let gal_fuses = vec![true; main_len];
+        let gal_xor = vec![true; spec.xor_size];
+        let gal_s1 = vec![true; spec.xor_size];
+        let gal_sig = vec![true; chips::SIG_SIZE];
+        let gal_ac1 = vec![true; chips::AC1_SIZE];
+        let gal_pt = vec![true; chips::PT_SIZE];
+
+        let jedec = make_jedec(
+            gal_type,
+            &config(),
+            &gal_fuses,
+            &gal_xor,
+            &gal_s1,
+            &gal_sig,
+            &gal_ac1,
+            &gal_pt,
+            true,
+            true,
+        );
+
+        let start = jedec.find("*C").unwrap() + 2;
+        let len = jedec[start..].find('\n').unwrap();
+        assert_eq!(&jedec[start..start + len], want_fuse_checksum);
+
+        let etx = jedec.find('\x03').unwrap();
+        assert_eq!(jedec[etx + 1..].trim(), want_transmission_checksum);
+    }
+
+    #[test]
+    fn checksums_match_known_good_values_for_every_chip() {
+        check_chip(Chip::GAL16V8, "10f1", "21d8");
+        check_chip(Chip::GAL20V8, "50b1", "82b5");
+        check_chip(Chip::GAL22V10, "dd2f", "4293");
+        check_chip(Chip::GAL20RA10, "976a", "00d0");
+    }
+}
@@ -0,0 +1,241 @@
+//
+// messages.rs: Locale-aware message catalog.
+//
+// errors.rs exists partly so we can "do internationalisation"; this is
+// where that actually happens. Each `ErrorCode` maps to a template
+// string per `Locale`, with positional `{0}`, `{1}`, ... placeholders
+// so a translation can reorder arguments instead of being stuck with
+// English word order. English is the built-in default, and any code
+// untranslated in another locale falls back to its English template.
+//
+
+use std::env;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use errors::ErrorCode;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    De,
+}
+
+impl Locale {
+    // Parse a `LANG`/`LC_MESSAGES`-style tag, e.g. "de_DE.UTF-8" or "de".
+    fn from_tag(tag: &str) -> Option<Locale> {
+        match tag.split(|c| c == '_' || c == '.').next() {
+            Some("de") => Some(Locale::De),
+            Some("en") => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    fn code(self) -> u8 {
+        match self {
+            Locale::En => 1,
+            Locale::De => 2,
+        }
+    }
+
+    fn from_code(code: u8) -> Option<Locale> {
+        match code {
+            1 => Some(Locale::En),
+            2 => Some(Locale::De),
+            _ => None,
+        }
+    }
+}
+
+fn locale_from_env() -> Locale {
+    // POSIX treats an empty LC_MESSAGES/LANG as "unset", not "the C/POSIX
+    // locale" — fall through to the next variable rather than treating
+    // the empty string as a tag to parse.
+    env::var("LC_MESSAGES")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .or_else(|| env::var("LANG").ok().filter(|s| !s.is_empty()))
+        .and_then(|tag| Locale::from_tag(&tag))
+        .unwrap_or(Locale::En)
+}
+
+// 0 means "not yet resolved from the environment".
+static ACTIVE_LOCALE: AtomicU8 = AtomicU8::new(0);
+
+// Override the active locale. Embedders call this to pick a locale
+// explicitly instead of relying on the environment.
+pub fn set_locale(locale: Locale) {
+    ACTIVE_LOCALE.store(locale.code(), Ordering::Relaxed);
+}
+
+// The active locale: whatever `set_locale` last set, or else whatever
+// `LC_MESSAGES`/`LANG` said at the time of the first call.
+pub fn locale() -> Locale {
+    if let Some(locale) = Locale::from_code(ACTIVE_LOCALE.load(Ordering::Relaxed)) {
+        return locale;
+    }
+    let detected = locale_from_env();
+    ACTIVE_LOCALE.store(detected.code(), Ordering::Relaxed);
+    detected
+}
+
+// A catalog maps an `ErrorCode` to its template string in one locale.
+// Codes with no entry here fall back to the English catalog.
+trait MessageCatalog {
+    fn template(&self, code: ErrorCode) -> Option<&'static str>;
+}
+
+struct English;
+
+impl MessageCatalog for English {
+    fn template(&self, code: ErrorCode) -> Option<&'static str> {
+        Some(match code {
+            ErrorCode::ARSPAsPinName => "GAL22V10: AR and SP is not allowed as pinname",
+            ErrorCode::ARSPSuffix => "AR, SP: no suffix allowed",
+            ErrorCode::BadAnalysis => "internal error: analyse_mode should never let you use this pin as an input",
+            ErrorCode::BadARSP => "use of AR and SP is not allowed in equations",
+            ErrorCode::BadNC => "NC (Not Connected) is not allowed in logic equations",
+            ErrorCode::BadChar => "bad character in input",
+            ErrorCode::BadEOF => "unexpected end of file",
+            ErrorCode::BadEOL => "unexpected end of line",
+            ErrorCode::BadFuseAddress => "fuse address in *L field is out of range",
+            ErrorCode::BadFuseChecksum => "*C fuse checksum does not match the fuses in the JEDEC file",
+            ErrorCode::BadGALType => "Line  1: type of GAL expected",
+            ErrorCode::BadPin => "illegal character in pin declaration",
+            ErrorCode::BadPinCount => "wrong number of pins",
+            ErrorCode::BadPower => "use of VCC and GND is not allowed in equations",
+            ErrorCode::BadSuffix => "unknown suffix found",
+            ErrorCode::BadToken => "unexpected token",
+            ErrorCode::InvertedARSP => "negation of AR and SP is not allowed",
+            ErrorCode::InvalidControl => "use of .CLK, .ARST, .APRST only allowed for registered outputs",
+            ErrorCode::InvertedControl => ".E, .CLK, .ARST and .APRST is not allowed to be negated",
+            ErrorCode::InvertedPower => "use GND, VCC instead of /VCC, /GND",
+            ErrorCode::MoreThanOneProduct => "only one product term allowed (no OR)",
+            ErrorCode::NotAnInputPin(_) => "GAL20RA10: pin {0} can't be used in equations",
+            ErrorCode::NotAnInputClockOE(_, _) => "mode 3: pins {0},{1} are reserved for 'Clock' and '/OE'",
+            ErrorCode::NotAnInputMode2(_, _) => "mode 2: pins {0}, {1} can't be used as input",
+            ErrorCode::NotAnOutput => "this pin can't be used as output",
+            ErrorCode::NoCLK => "missing clock definition (.CLK) of registered output",
+            ErrorCode::NoPinName => "pinname expected after '/'",
+            ErrorCode::NoEquals => "'=' expected",
+            ErrorCode::RepeatedAPRST => "several .APRST definitions for the same output found",
+            ErrorCode::RepeatedARST => "several .ARST definitions for the same output found",
+            ErrorCode::RepeatedARSP => "AR or SP is defined twice",
+            ErrorCode::RepeatedCLK => "several .CLK definitions for the same output found",
+            ErrorCode::RepeatedOutput => "same pin is defined multible as output",
+            ErrorCode::RepeatedPinName => "pinname defined twice",
+            ErrorCode::RepeatedTristate => "tristate control is defined twice",
+            ErrorCode::SoloAPRST => "if using .APRST the output must be defined",
+            ErrorCode::SoloARST => "if using .ARST, the output must be defined",
+            ErrorCode::SoloCLK => "if using .CLK, the output must be defined",
+            ErrorCode::SoloEnable => "if using .E, the output must be defined",
+            ErrorCode::TooManyProducts => "too many product terms",
+            ErrorCode::TristateReg => "GAL16V8/20V8: tri. control for reg. output is not allowed",
+            ErrorCode::UnknownPin => "unknown pinname",
+            ErrorCode::UnmatchedTristate => "tristate control without previous '.T'",
+            ErrorCode::BadVCC => "pin declaration: expected VCC at VCC pin",
+            ErrorCode::BadVCCLocation => "illegal VCC/GND assignment",
+            ErrorCode::BadGND => "pin declaration: expected GND at GND pin",
+            ErrorCode::BadGNDLocation => "illegal VCC/GND assignment",
+            ErrorCode::BadJedecFrame => "JEDEC file is missing its STX/ETX framing",
+            ErrorCode::BadTransmissionChecksum => "JEDEC transmission checksum does not match the file contents",
+            ErrorCode::DisallowedCLK => ".CLK is not allowed when this type of GAL is used",
+            ErrorCode::DisallowedARST => ".ARST is not allowed when this type of GAL is used",
+            ErrorCode::DisallowedAPRST => ".APRST is not allowed when this type of GAL is used",
+        })
+    }
+}
+
+// Only a handful of messages are translated so far; everything else
+// falls back to English.
+struct German;
+
+impl MessageCatalog for German {
+    fn template(&self, code: ErrorCode) -> Option<&'static str> {
+        match code {
+            ErrorCode::BadChar => Some("ungueltiges Zeichen in der Eingabe"),
+            ErrorCode::BadEOF => Some("unerwartetes Dateiende"),
+            ErrorCode::BadEOL => Some("unerwartetes Zeilenende"),
+            ErrorCode::UnknownPin => Some("unbekannter Pinname"),
+            _ => None,
+        }
+    }
+}
+
+fn catalog(locale: Locale) -> &'static dyn MessageCatalog {
+    match locale {
+        Locale::En => &English,
+        Locale::De => &German,
+    }
+}
+
+// Positional arguments for an ErrorCode's template, formatted as
+// strings so the substitution code doesn't need to know each code's
+// field types.
+fn args(code: ErrorCode) -> Vec<String> {
+    match code {
+        ErrorCode::NotAnInputPin(pin) => vec![pin.to_string()],
+        ErrorCode::NotAnInputClockOE(a, b) => vec![a.to_string(), b.to_string()],
+        ErrorCode::NotAnInputMode2(a, b) => vec![a.to_string(), b.to_string()],
+        _ => Vec::new(),
+    }
+}
+
+// Substitute `{0}`, `{1}`, ... placeholders in `template` with `args`.
+fn substitute(template: &str, args: &[String]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let end = after.find('}').expect("malformed message template");
+        let index: usize = after[..end]
+            .parse()
+            .expect("malformed message template placeholder");
+        out.push_str(&args[index]);
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+// Render `code` in `locale`, falling back to English for any code the
+// target locale hasn't translated yet.
+pub fn error_string_in(code: ErrorCode, locale: Locale) -> String {
+    let template = catalog(locale)
+        .template(code)
+        .or_else(|| catalog(Locale::En).template(code))
+        .expect("English catalog covers every ErrorCode");
+    substitute(template, &args(code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substitute_fills_in_multiple_positional_args() {
+        let rendered = substitute(
+            "mode 3: pins {0},{1} are reserved for 'Clock' and '/OE'",
+            &["12".to_string(), "15".to_string()],
+        );
+        assert_eq!(
+            rendered,
+            "mode 3: pins 12,15 are reserved for 'Clock' and '/OE'"
+        );
+    }
+
+    #[test]
+    fn from_tag_parses_german_and_rejects_unknown_tags() {
+        assert_eq!(Locale::from_tag("de_DE.UTF-8"), Some(Locale::De));
+        assert_eq!(Locale::from_tag("de"), Some(Locale::De));
+        assert_eq!(Locale::from_tag("fr_FR.UTF-8"), None);
+    }
+
+    #[test]
+    fn german_falls_back_to_english_for_untranslated_codes() {
+        // NotAnInputPin has no German template, so this should be the
+        // English template with the pin number substituted in.
+        let rendered = error_string_in(ErrorCode::NotAnInputPin(0), Locale::De);
+        assert_eq!(rendered, "GAL20RA10: pin 0 can't be used in equations");
+    }
+}